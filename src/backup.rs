@@ -0,0 +1,115 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDateTime};
+use dialoguer::Select;
+
+use crate::error::UpdaterError;
+
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+const SINGLE_BACKUP_NAME: &str = "user-backup.js";
+
+/// Finds every `user-backup-<timestamp>.js` file in the current directory,
+/// newest first.
+pub fn find_backups() -> Result<Vec<(NaiveDateTime, PathBuf)>, UpdaterError> {
+    let mut backups = Vec::new();
+
+    for entry in fs::read_dir(".")? {
+        let path = entry?.path();
+        let name = match path.file_name().and_then(|n| n.to_str()) {
+            Some(name) => name,
+            None => continue
+        };
+
+        let timestamp = name.strip_prefix("user-backup-")
+            .and_then(|rest| rest.strip_suffix(".js"));
+        if let Some(timestamp) = timestamp {
+            if let Ok(date) = NaiveDateTime::parse_from_str(timestamp, BACKUP_TIMESTAMP_FORMAT) {
+                backups.push((date, path));
+            }
+        }
+    }
+
+    backups.sort_by(|(a, _), (b, _)| b.cmp(a));
+    Ok(backups)
+}
+
+/// Renames `user.js` to a backup file. When `single` is set, the backup
+/// always overwrites the same `user-backup.js` file instead of piling up
+/// a new timestamped one.
+pub fn backup_current_script(single: bool) -> Result<PathBuf, UpdaterError> {
+    let backup_name = if single {
+        PathBuf::from(SINGLE_BACKUP_NAME)
+    } else {
+        let time = Local::now().format(BACKUP_TIMESTAMP_FORMAT);
+        PathBuf::from(format!("user-backup-{}.js", time))
+    };
+
+    if single && backup_name.exists() {
+        fs::remove_file(&backup_name)?;
+    }
+    fs::rename("user.js", &backup_name)?;
+    Ok(backup_name)
+}
+
+pub fn list_backups() -> Result<(), UpdaterError> {
+    let backups = find_backups()?;
+    if backups.is_empty() {
+        println!("No backups found in the current directory.");
+        return Ok(());
+    }
+
+    for (date, path) in &backups {
+        println!("{} - {}", date.format("%Y-%m-%d %H:%M:%S"), path.display());
+    }
+
+    Ok(())
+}
+
+pub fn clean_backups(keep: usize) -> Result<(), UpdaterError> {
+    let backups = find_backups()?;
+    if backups.len() <= keep {
+        println!("Nothing to clean, {} backup(s) found and {} are kept.", backups.len(), keep);
+        return Ok(());
+    }
+
+    for (_, path) in backups.iter().skip(keep) {
+        println!("Removing {}...", path.display());
+        fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+pub fn rollback() -> Result<(), UpdaterError> {
+    let backups = find_backups()?;
+    if backups.is_empty() {
+        println!("No backups found in the current directory.");
+        return Ok(());
+    }
+
+    let items: Vec<String> = backups.iter()
+        .map(|(date, _)| date.format("%Y-%m-%d %H:%M:%S").to_string())
+        .collect();
+
+    let selection = Select::new()
+        .with_prompt("Choose a backup to restore")
+        .items(&items)
+        .interact_opt()?;
+
+    let (_, path) = match selection {
+        Some(index) => &backups[index],
+        None => return Ok(())
+    };
+
+    if Path::new("user.js").exists() {
+        let backup_name = backup_current_script(false)?;
+        println!("Backed up current user.js to {}", backup_name.display());
+    }
+
+    println!("Restoring {}...", path.display());
+    fs::copy(path, "user.js")?;
+    println!("Rollback complete!");
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use std::fs;
+use std::path::PathBuf;
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+
+use crate::error::UpdaterError;
+use crate::version::Version;
+
+const CACHE_FILE_NAME: &str = "cache.toml";
+
+/// The last successfully fetched upstream script, kept so `--offline` can
+/// source an update without a network connection.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Cache {
+    pub body: String,
+    pub version: Version,
+}
+
+fn cache_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "ghacks-updater")
+        .map(|dirs| dirs.cache_dir().join(CACHE_FILE_NAME))
+}
+
+/// Loads the cached copy, if one has ever been stored.
+pub fn load() -> Result<Option<Cache>, UpdaterError> {
+    let path = match cache_path() {
+        Some(path) if path.exists() => path,
+        _ => return Ok(None)
+    };
+
+    let contents = fs::read_to_string(&path)?;
+    let cache = toml::from_str(&contents)
+        .map_err(|e| UpdaterError::ParseError(format!("invalid cache at {}: {}", path.display(), e)))?;
+
+    Ok(Some(cache))
+}
+
+/// Persists a freshly fetched script and its parsed version to the cache.
+pub fn store(body: &str, version: &Version) -> Result<(), UpdaterError> {
+    let path = cache_path()
+        .ok_or_else(|| UpdaterError::ParseError("could not determine the cache directory".to_string()))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let cache = Cache { body: body.to_string(), version: version.clone() };
+    let serialized = toml::to_string(&cache)
+        .map_err(|e| UpdaterError::ParseError(format!("failed to serialize cache: {}", e)))?;
+
+    fs::write(path, serialized)?;
+    Ok(())
+}
+
+/// Discards the cached copy, if any.
+pub fn clear() -> Result<(), UpdaterError> {
+    if let Some(path) = cache_path() {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+
+use crate::error::UpdaterError;
+
+const CONFIG_FILE_NAME: &str = "ghacks-updater.toml";
+
+/// Defaults read from `ghacks-updater.toml`. Any value a user also passes
+/// as a CLI flag is overridden by the flag.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Config {
+    #[serde(default)]
+    pub minify: bool,
+    #[serde(default)]
+    pub single_backup: bool,
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub profile_path: Option<PathBuf>,
+    #[serde(default)]
+    pub backup_retention: Option<usize>,
+}
+
+impl Config {
+    /// Loads `ghacks-updater.toml`, preferring a copy in `profile_dir` and
+    /// falling back to the platform config directory. Returns the default
+    /// (empty) config if neither location has a file, so running the tool
+    /// without ever writing a config stays a no-op.
+    pub fn load(profile_dir: &Path) -> Result<Config, UpdaterError> {
+        let profile_config = profile_dir.join(CONFIG_FILE_NAME);
+        if profile_config.exists() {
+            return Config::read(&profile_config);
+        }
+
+        if let Some(path) = Config::platform_config_path() {
+            if path.exists() {
+                return Config::read(&path);
+            }
+        }
+
+        Ok(Config::default())
+    }
+
+    fn read(path: &Path) -> Result<Config, UpdaterError> {
+        let contents = fs::read_to_string(path)?;
+        toml::from_str(&contents)
+            .map_err(|e| UpdaterError::ParseError(format!("invalid config at {}: {}", path.display(), e)))
+    }
+
+    fn platform_config_path() -> Option<PathBuf> {
+        ProjectDirs::from("", "", "ghacks-updater")
+            .map(|dirs| dirs.config_dir().join(CONFIG_FILE_NAME))
+    }
+}
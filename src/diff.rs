@@ -0,0 +1,103 @@
+use crate::prefs::extract_prefs;
+
+/// The set difference between an old and a new `user_pref` map.
+#[derive(Debug, Default)]
+pub struct PrefDiff {
+    pub added: Vec<(String, String)>,
+    pub removed: Vec<(String, String)>,
+    pub changed: Vec<(String, String, String)>,
+}
+
+impl PrefDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Diffs the `user_pref` entries of two `user.js`-style files.
+pub fn diff_prefs(old: &str, new: &str) -> PrefDiff {
+    let old_prefs = extract_prefs(old);
+    let new_prefs = extract_prefs(new);
+
+    let mut added: Vec<(String, String)> = new_prefs.iter()
+        .filter(|(key, _)| !old_prefs.contains_key(*key))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+
+    let mut removed: Vec<(String, String)> = old_prefs.iter()
+        .filter(|(key, _)| !new_prefs.contains_key(*key))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+
+    let mut changed: Vec<(String, String, String)> = old_prefs.iter()
+        .filter_map(|(key, old_value)| {
+            new_prefs.get(key)
+                .filter(|new_value| *new_value != old_value)
+                .map(|new_value| (key.clone(), old_value.clone(), new_value.clone()))
+        })
+        .collect();
+
+    added.sort();
+    removed.sort();
+    changed.sort();
+
+    PrefDiff { added, removed, changed }
+}
+
+pub fn print_diff(diff: &PrefDiff) {
+    if diff.is_empty() {
+        println!("No pref changes.");
+        return;
+    }
+
+    println!(
+        "Pref changes: {} added, {} removed, {} changed",
+        diff.added.len(), diff.removed.len(), diff.changed.len()
+    );
+
+    if !diff.added.is_empty() {
+        println!("\nAdded:");
+        for (key, value) in &diff.added {
+            println!("  {} = {}", key, value);
+        }
+    }
+
+    if !diff.removed.is_empty() {
+        println!("\nRemoved:");
+        for (key, value) in &diff.removed {
+            println!("  {} = {}", key, value);
+        }
+    }
+
+    if !diff.changed.is_empty() {
+        println!("\nChanged:");
+        for (key, old_value, new_value) in &diff.changed {
+            println!("  {}: {} -> {}", key, old_value, new_value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_prefs_is_empty_for_identical_files() {
+        let script = "user_pref(\"a.b\", true);\nuser_pref(\"c.d\", 1);";
+        let diff = diff_prefs(script, script);
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn diff_prefs_detects_added_removed_and_changed_prefs() {
+        let old = "user_pref(\"a.b\", true);\nuser_pref(\"c.d\", 1);";
+        let new = "user_pref(\"a.b\", false);\nuser_pref(\"e.f\", \"x\");";
+
+        let diff = diff_prefs(old, new);
+
+        assert_eq!(diff.added, vec![("e.f".to_string(), "\"x\"".to_string())]);
+        assert_eq!(diff.removed, vec![("c.d".to_string(), "1".to_string())]);
+        assert_eq!(diff.changed, vec![("a.b".to_string(), "true".to_string(), "false".to_string())]);
+        assert!(!diff.is_empty());
+    }
+}
@@ -0,0 +1,31 @@
+use std::error::Error;
+use std::fmt;
+use std::fmt::Formatter;
+
+#[derive(Debug)]
+pub enum UpdaterError {
+    MissingScript,
+    MissingOverrides,
+    ParseError(String),
+    IoError(std::io::Error),
+    NetworkError(reqwest::Error)
+}
+
+impl From<std::io::Error> for UpdaterError {
+    fn from(e: std::io::Error) -> Self {
+        UpdaterError::IoError(e)
+    }
+}
+
+impl Error for UpdaterError { }
+impl fmt::Display for UpdaterError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            UpdaterError::MissingScript => write!(f, "user.js not detected in the current directory."),
+            UpdaterError::MissingOverrides => write!(f, "user-overrides.js not detected in the current directory."),
+            UpdaterError::ParseError(context) => write!(f, "Error parsing input: {}", context),
+            UpdaterError::IoError(e) => write!(f, "IO Error: {}", e),
+            UpdaterError::NetworkError(e) => write!(f, "Network error: {}", e)
+        }
+    }
+}
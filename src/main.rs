@@ -1,151 +1,86 @@
+mod backup;
+mod cache;
+mod config;
+mod diff;
+mod error;
+mod fetcher;
+mod prefs;
+mod version;
+
 use structopt::StructOpt;
 use std::fs::File;
-use std::error::Error;
-use std::{fmt, fs};
-use std::fmt::Formatter;
-use std::io::{ErrorKind, BufReader, BufRead, Read, BufWriter, Write};
+use std::fs;
+use std::io::{ErrorKind, BufReader, Read, BufWriter, Write};
 use dialoguer::Select;
-use std::path::Path;
-use chrono::Local;
-use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
-const URL: &'static str = "https://raw.githubusercontent.com/ghacksuserjs/ghacks-user.js/master/user.js";
+use config::Config;
+use diff::{diff_prefs, print_diff};
+use error::UpdaterError;
+use fetcher::Source;
+use prefs::{minify, PrefConflict};
 
 #[derive(Debug, StructOpt)]
-#[structopt(name = "args", about = "The inputs for the script")]
+#[structopt(name = "ghacks-updater", about = "Keep your Firefox user.js hardening prefs up to date")]
 struct Arguments {
+    /// Firefox profile directory to operate in. Defaults to the
+    /// `profile_path` in ghacks-updater.toml, or the current directory
+    #[structopt(long, parse(from_os_str))]
+    profile: Option<PathBuf>,
+
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Download the latest user.js and merge in your overrides
+    Update(UpdateArgs),
+    /// Restore a previous user.js backup
+    Rollback,
+    /// List the available user.js backups
+    ListBackups,
+    /// Delete old backups, keeping only the most recent ones
+    CleanBackups(CleanBackupsArgs),
+    /// Discard the cached copy of the upstream script
+    ClearCache,
+}
+
+#[derive(Debug, StructOpt)]
+struct UpdateArgs {
     #[structopt(short, long)]
     unattended: bool,
+    /// Merge overrides into the upstream file instead of appending them.
+    /// Overrides ghacks-updater.toml's `minify` when passed
     #[structopt(short, long)]
     minify: bool,
+    /// Overrides ghacks-updater.toml's `single_backup` when passed
     #[structopt(long = "singlebackup")]
     single_backup: bool,
+    /// Which hardened user.js project to pull from: ghacks, arkenfox, or
+    /// any other raw URL. Overrides ghacks-updater.toml's `source` when
+    /// passed
+    #[structopt(long)]
+    source: Option<Source>,
+    /// Compute and print the pref diff without writing any files
+    #[structopt(long)]
+    dry_run: bool,
+    /// Fail instead of warning when merging overrides produces a
+    /// conflicting or duplicated pref
+    #[structopt(long)]
+    strict: bool,
+    /// Update from the last cached copy of the upstream script instead of
+    /// downloading a new one
+    #[structopt(long)]
+    offline: bool,
 }
 
-#[derive(Debug)]
-enum UpdaterError {
-    MissingScript,
-    MissingOverrides,
-    ParseError(String),
-    IoError(std::io::Error),
-    NetworkError(reqwest::Error)
-}
-
-impl From<std::io::Error> for UpdaterError {
-    fn from(e: std::io::Error) -> Self {
-        UpdaterError::IoError(e)
-    }
-}
-
-impl Error for UpdaterError { }
-impl fmt::Display for UpdaterError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            UpdaterError::MissingScript => write!(f, "user.js not detected in the current directory."),
-            UpdaterError::MissingOverrides => write!(f, "user-overrides.js not detected in the current directory."),
-            UpdaterError::ParseError(context) => write!(f, "Error parsing input: {}", context),
-            UpdaterError::IoError(e) => write!(f, "IO Error: {}", e),
-            UpdaterError::NetworkError(e) => write!(f, "Network error: {}", e)
-        }
-    }
-}
-
-#[derive(Debug, PartialEq)]
-struct Version {
-    name: String,
-    version: String,
-    date: String
-}
-
-impl fmt::Display for Version {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}: {} from {}", self.name, self.version, self.date)
-    }
-}
-
-fn get_version_info(file: &mut BufReader<File>) -> Result<Version, UpdaterError> {
-    let mut void = String::new();
-    file.read_line(&mut void)?; // Start of comment
-
-    let mut name = String::new();
-    file.read_line(&mut name)?; // Name in format '* name: ghacks user.js'
-    let name = name.split("name: ")
-        .skip(1).next().unwrap();
-    let mut date = String::new();
-    file.read_line(&mut date)?; // Date in format '* date: 14 February 2020'
-    let date = date
-        .split("date: ")
-        .skip(1).next().unwrap();
-    let mut version = String::new();
-    file.read_line(&mut version)?;
-    let version = version.split("version ")
-        .skip(1).next().unwrap();
-
-    if !name.contains("ghacks") {
-        return Err(UpdaterError::ParseError("Version not recognized".to_string()));
-    }
-
-    Ok(Version {
-        name: name.to_string().replace("\n", ""),
-        version: version.to_string().replace("\n", ""),
-        date: date.to_string().replace("\n", "")
-    })
-}
-
-async fn fetch_script() -> Result<String, UpdaterError> {
-    println!("Retrieving latest user.js file from github repository...");
-    let res = reqwest::get(URL).await
-        .map_err(UpdaterError::NetworkError)?;
-
-    Ok(res.text().await.unwrap())
-}
-
-fn extract_pref(line: &String) -> (String, String) {
-    let pref: &str = line[10..].split(")").next().unwrap();
-    let mut pref_iter = pref.split(",");
-    let key: String = pref_iter.next().unwrap().replace("\"", "");
-    let key = key.trim();
-    let value: &str = pref_iter.next().unwrap().trim();
-    (key.to_string(), value.to_string())
-}
-
-fn minify(original: String, overrides: String) -> Result<String, UpdaterError> {
-    let original_reader = BufReader::new(original.as_bytes());
-    let overrides_reader = BufReader::new(overrides.as_bytes());
-
-    let original_lines: Vec<String> = original_reader.lines()
-        .filter_map(Result::ok)
-        .collect();
-    let overrides_lines: Vec<String> = overrides_reader.lines()
-        .filter_map(Result::ok)
-        .collect();
-
-    let header: Vec<String> = original_lines
-        .iter()
-        .take(76)
-        .map(Clone::clone)
-        .collect();
-    let header = header.join("\n");
-
-    let mut entries: HashMap<String, String> = original_lines.iter()
-        .filter(|line| line.starts_with("user_pref("))
-        .map(extract_pref)
-        .collect();
-
-    let override_entries = overrides_lines.iter()
-        .filter(|line| line.starts_with("user_pref("))
-        .map(extract_pref);
-
-    for (key, value) in override_entries {
-        entries.insert(key, value);
-    }
-
-    let prefs: Vec<String> = entries.into_iter()
-        .map(|(key, value)| format!("user_pref(\"{}\", {});", key, value))
-        .collect();
-    let prefs = prefs.join("\n");
-
-    Ok(format!("{}\n\n{}", header, prefs))
+#[derive(Debug, StructOpt)]
+struct CleanBackupsArgs {
+    /// Number of most recent backups to keep. Overrides
+    /// ghacks-updater.toml's `backup_retention` when passed
+    #[structopt(long)]
+    keep: Option<usize>,
 }
 
 #[tokio::main]
@@ -163,16 +98,45 @@ async fn main() -> Result<(), ()> {
 async fn run() -> Result<(), UpdaterError> {
     let args: Arguments = Arguments::from_args();
 
-    let version = {
-        let file = File::open("user.js")
-            .map_err(|e| match e.kind() {
-                ErrorKind::NotFound => UpdaterError::MissingScript,
-                _ => panic!("Unknown error occurred: {}", e)
-            })?;
-        let mut file = BufReader::new(file);
+    let config_dir = args.profile.clone().unwrap_or_else(|| PathBuf::from("."));
+    let config = Config::load(&config_dir)?;
 
-        get_version_info(&mut file)?
+    let profile_dir = args.profile
+        .or_else(|| config.profile_path.clone())
+        .unwrap_or_else(|| PathBuf::from("."));
+    if profile_dir != Path::new(".") {
+        std::env::set_current_dir(&profile_dir)?;
+    }
+
+    match args.command {
+        Command::Update(update_args) => update(update_args, &config).await,
+        Command::Rollback => backup::rollback(),
+        Command::ListBackups => backup::list_backups(),
+        Command::CleanBackups(clean_args) => {
+            let keep = clean_args.keep.or(config.backup_retention).unwrap_or(5);
+            backup::clean_backups(keep)
+        }
+        Command::ClearCache => cache::clear(),
+    }
+}
+
+async fn update(args: UpdateArgs, config: &Config) -> Result<(), UpdaterError> {
+    let should_minify = args.minify || config.minify;
+    let single_backup = args.single_backup || config.single_backup;
+    let source = match args.source {
+        Some(source) => source,
+        None => match &config.source {
+            Some(source) => source.parse()?,
+            None => Source::default(),
+        }
     };
+
+    let old_contents = fs::read_to_string("user.js")
+        .map_err(|e| match e.kind() {
+            ErrorKind::NotFound => UpdaterError::MissingScript,
+            _ => UpdaterError::IoError(e)
+        })?;
+    let version = source.fetcher().parse_version(&old_contents)?;
     println!("Found version: {}", version);
 
     if !args.unattended {
@@ -198,7 +162,19 @@ async fn run() -> Result<(), UpdaterError> {
         }
     }
 
-    let new_script = fetch_script().await?;
+    let new_script = if args.offline {
+        let cached = cache::load()?
+            .ok_or_else(|| UpdaterError::ParseError("no cached copy available; run update once online first".to_string()))?;
+        println!("Using cached copy: {}", cached.version);
+        cached.body
+    } else {
+        source.fetcher().fetch().await?
+    };
+    let new_version = source.fetcher().parse_version(&new_script)?;
+
+    if !args.offline && !args.dry_run {
+        cache::store(&new_script, &new_version)?;
+    }
 
     let mut user_overrides = String::from("\n");
     let user_overrides_path = Path::new("user-overrides.js");
@@ -209,46 +185,56 @@ async fn run() -> Result<(), UpdaterError> {
         return Err(UpdaterError::MissingOverrides);
     }
 
-    {
-        let mut new_file = BufWriter::new(File::create("user.js.new")?);
-        if args.minify {
-            let new_string = minify(new_script, user_overrides)?;
-            new_file.write_all(new_string.as_bytes())?;
-        } else {
-            new_file.write_all(new_script.as_bytes())?;
-            new_file.write_all(user_overrides.as_bytes())?;
+    let new_content = if should_minify {
+        let (content, conflicts) = minify(new_script, user_overrides)?;
+        if !conflicts.is_empty() {
+            if args.strict {
+                let details = conflicts.iter()
+                    .map(PrefConflict::to_string)
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                return Err(UpdaterError::ParseError(format!("conflicting prefs: {}", details)));
+            }
+
+            eprintln!("Warning: {} conflicting pref(s) found while merging overrides:", conflicts.len());
+            for conflict in &conflicts {
+                eprintln!("  {}", conflict);
+            }
         }
+        content
+    } else {
+        format!("{}{}", new_script, user_overrides)
+    };
+
+    if !new_version.is_newer_than(&version) {
+        println!("Already up to date (local: {}, upstream: {}).", version, new_version);
+        return Ok(());
     }
 
-    let new_version = {
-        let file = File::open("user.js.new")?;
-        let mut file = BufReader::new(file);
+    let diff = diff_prefs(&old_contents, &new_content);
 
-        get_version_info(&mut file)?
-    };
-    let changed = version == new_version;
-    if changed {
-        println!(r#"
-            Version changed
-            Old version: {},
-            New version: {}
-        "#, version, new_version);
+    if diff.is_empty() {
+        println!("Already up to date (local: {}, upstream: {}).", version, new_version);
+        return Ok(());
     }
 
-    if changed {
-        let current_time = Local::now();
-        let time = current_time.format("%Y-%m-%d_%H-%M-%S");
-        let backup_name = format!("user-backup-{}.js", time);
-        println!("Backing up to {}", backup_name);
-        fs::rename("user.js", backup_name)?;
-        println!("Renaming new file...");
-        fs::rename("user.js.new", "user.js")?;
-        println!("Update complete!")
-    } else {
-        fs::remove_file("user.js.new")?;
-        println!("Update completed without any changes");
+    print_diff(&diff);
+
+    if args.dry_run {
+        println!("Dry run: no files were written.");
+        return Ok(());
     }
 
+    {
+        let mut new_file = BufWriter::new(File::create("user.js.new")?);
+        new_file.write_all(new_content.as_bytes())?;
+    }
+    let backup_name = backup::backup_current_script(single_backup)?;
+    println!("Backed up to {}", backup_name.display());
+    println!("Renaming new file...");
+    fs::rename("user.js.new", "user.js")?;
+    println!("Update complete!");
+
     Ok(())
 }
 
@@ -273,4 +259,4 @@ fn show_help() -> Result<(), UpdaterError> {
     "#);
 
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Formatter;
+use std::io::{BufRead, BufReader};
+
+use crate::error::UpdaterError;
+
+/// A `user_pref` key that was defined more than once while merging
+/// overrides, with a different value each time.
+#[derive(Debug)]
+pub struct PrefConflict {
+    pub key: String,
+    pub prior_value: String,
+    pub new_value: String,
+    pub within_overrides: bool,
+}
+
+impl fmt::Display for PrefConflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let context = if self.within_overrides {
+            "duplicated in user-overrides.js"
+        } else {
+            "overridden from upstream"
+        };
+        write!(f, "{} is defined more than once ({}): {} -> {}", self.key, context, self.prior_value, self.new_value)
+    }
+}
+
+pub fn extract_pref(line: &str) -> (String, String) {
+    let pref: &str = line[10..].split(")").next().unwrap();
+    let mut pref_iter = pref.split(",");
+    let key: String = pref_iter.next().unwrap().replace("\"", "");
+    let key = key.trim();
+    let value: &str = pref_iter.next().unwrap().trim();
+    (key.to_string(), value.to_string())
+}
+
+/// Parses every `user_pref(...)` line out of a `user.js`-style file into a
+/// key/value map, used by the diff report.
+pub fn extract_prefs(contents: &str) -> HashMap<String, String> {
+    BufReader::new(contents.as_bytes())
+        .lines()
+        .map_while(Result::ok)
+        .filter(|line| line.starts_with("user_pref("))
+        .map(|line| extract_pref(&line))
+        .collect()
+}
+
+pub fn minify(original: String, overrides: String) -> Result<(String, Vec<PrefConflict>), UpdaterError> {
+    let original_reader = BufReader::new(original.as_bytes());
+    let overrides_reader = BufReader::new(overrides.as_bytes());
+
+    let original_lines: Vec<String> = original_reader.lines()
+        .map_while(Result::ok)
+        .collect();
+    let overrides_lines: Vec<String> = overrides_reader.lines()
+        .map_while(Result::ok)
+        .collect();
+
+    // The header is whatever comment/boilerplate precedes the first
+    // `user_pref(...)` line. Finding the boundary this way (rather than a
+    // fixed line count) keeps this working across sources with
+    // differently-sized headers (ghacks, Arkenfox, custom URLs).
+    let header_len = original_lines.iter()
+        .position(|line| line.starts_with("user_pref("))
+        .unwrap_or(original_lines.len());
+    let header = original_lines[..header_len].join("\n");
+
+    let mut entries: HashMap<String, String> = original_lines.iter()
+        .filter(|line| line.starts_with("user_pref("))
+        .map(|line| extract_pref(line))
+        .collect();
+
+    let override_entries = overrides_lines.iter()
+        .filter(|line| line.starts_with("user_pref("))
+        .map(|line| extract_pref(line));
+
+    let mut conflicts: Vec<PrefConflict> = Vec::new();
+    let mut seen_in_overrides: HashMap<String, String> = HashMap::new();
+
+    for (key, value) in override_entries {
+        if let Some(prior) = seen_in_overrides.get(&key).filter(|prior| **prior != value) {
+            conflicts.push(PrefConflict {
+                key: key.clone(),
+                prior_value: prior.clone(),
+                new_value: value.clone(),
+                within_overrides: true,
+            });
+        } else if let Some(prior) = entries.get(&key).filter(|prior| **prior != value) {
+            conflicts.push(PrefConflict {
+                key: key.clone(),
+                prior_value: prior.clone(),
+                new_value: value.clone(),
+                within_overrides: false,
+            });
+        }
+
+        seen_in_overrides.insert(key.clone(), value.clone());
+        entries.insert(key, value);
+    }
+
+    let prefs: Vec<String> = entries.into_iter()
+        .map(|(key, value)| format!("user_pref(\"{}\", {});", key, value))
+        .collect();
+    let prefs = prefs.join("\n");
+
+    Ok((format!("{}\n\n{}", header, prefs), conflicts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minify_merges_overrides_without_conflicts() {
+        let original = "// header\nuser_pref(\"a.b\", true);".to_string();
+        let overrides = "user_pref(\"c.d\", 1);".to_string();
+
+        let (content, conflicts) = minify(original, overrides).unwrap();
+
+        assert!(conflicts.is_empty());
+        assert!(content.contains("user_pref(\"a.b\", true);"));
+        assert!(content.contains("user_pref(\"c.d\", 1);"));
+    }
+
+    #[test]
+    fn minify_reports_a_conflict_when_an_override_changes_an_upstream_pref() {
+        let original = "// header\nuser_pref(\"a.b\", true);".to_string();
+        let overrides = "user_pref(\"a.b\", false);".to_string();
+
+        let (_, conflicts) = minify(original, overrides).unwrap();
+
+        assert_eq!(conflicts.len(), 1);
+        assert!(!conflicts[0].within_overrides);
+        assert_eq!(conflicts[0].key, "a.b");
+    }
+
+    #[test]
+    fn minify_reports_a_conflict_when_overrides_duplicate_a_key_with_different_values() {
+        let original = "// header\nuser_pref(\"a.b\", true);".to_string();
+        let overrides = "user_pref(\"c.d\", 1);\nuser_pref(\"c.d\", 2);".to_string();
+
+        let (_, conflicts) = minify(original, overrides).unwrap();
+
+        assert_eq!(conflicts.len(), 1);
+        assert!(conflicts[0].within_overrides);
+        assert_eq!(conflicts[0].key, "c.d");
+    }
+}
@@ -0,0 +1,109 @@
+use std::fmt;
+use std::fmt::Formatter;
+use std::io::BufRead;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::UpdaterError;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Version {
+    pub name: String,
+    pub version: String,
+    pub date: String
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} from {}", self.name, self.version, self.date)
+    }
+}
+
+impl Version {
+    /// Splits the dotted version string into numeric components so two
+    /// versions can be compared ordinally instead of just for equality.
+    /// Non-numeric or missing components are treated as `0`.
+    fn components(&self) -> Vec<u64> {
+        self.version.split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    }
+
+    /// Whether this version is strictly newer than `other`. Component
+    /// vectors are padded to equal length first, so `"69"` and `"69.0"`
+    /// compare equal instead of the longer one winning on length alone.
+    pub fn is_newer_than(&self, other: &Version) -> bool {
+        let mut ours = self.components();
+        let mut theirs = other.components();
+        let len = ours.len().max(theirs.len());
+        ours.resize(len, 0);
+        theirs.resize(len, 0);
+        ours > theirs
+    }
+}
+
+/// Pulls the text following `marker` out of a header line, returning a
+/// `ParseError` rather than panicking when a fetched file doesn't use the
+/// expected layout (e.g. an unrecognized or malformed source).
+fn extract_field<'a>(line: &'a str, marker: &str) -> Result<&'a str, UpdaterError> {
+    line.split_once(marker)
+        .map(|(_, rest)| rest.trim_end_matches(['\n', '\r']))
+        .ok_or_else(|| UpdaterError::ParseError(format!("expected a '{}' header line", marker.trim())))
+}
+
+pub fn get_version_info(file: &mut impl BufRead) -> Result<Version, UpdaterError> {
+    let mut void = String::new();
+    file.read_line(&mut void)?; // Start of comment
+
+    let mut name_line = String::new();
+    file.read_line(&mut name_line)?; // Name in format '* name: ghacks user.js'
+    let name = extract_field(&name_line, "name: ")?;
+
+    let mut date_line = String::new();
+    file.read_line(&mut date_line)?; // Date in format '* date: 14 February 2020'
+    let date = extract_field(&date_line, "date: ")?;
+
+    let mut version_line = String::new();
+    file.read_line(&mut version_line)?;
+    let version = extract_field(&version_line, "version ")?;
+
+    Ok(Version {
+        name: name.to_string(),
+        version: version.to_string(),
+        date: date.to_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(v: &str) -> Version {
+        Version { name: "test".to_string(), version: v.to_string(), date: "today".to_string() }
+    }
+
+    #[test]
+    fn is_newer_than_detects_a_strictly_higher_version() {
+        assert!(version("107").is_newer_than(&version("106")));
+        assert!(version("1.2").is_newer_than(&version("1.1")));
+    }
+
+    #[test]
+    fn is_newer_than_is_false_for_an_older_version() {
+        assert!(!version("106").is_newer_than(&version("107")));
+    }
+
+    #[test]
+    fn is_newer_than_ignores_trailing_zero_components() {
+        assert!(!version("69").is_newer_than(&version("69.0")));
+        assert!(!version("69.0").is_newer_than(&version("69")));
+        assert!(!version("1.0").is_newer_than(&version("1")));
+    }
+
+    #[test]
+    fn get_version_info_rejects_a_header_missing_the_expected_markers() {
+        let mut reader = "/* comment\n * not a name line\n".as_bytes();
+        let result = get_version_info(&mut reader);
+        assert!(matches!(result, Err(UpdaterError::ParseError(_))));
+    }
+}